@@ -0,0 +1,549 @@
+//! The monotronian evaluator
+//!
+//! This module walks the AST produced by the `parser` module and executes it
+//! directly, without compiling to any intermediate form.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::parser::{Block, Expression, Identifier, Infix, Literal, Prefix, Statement};
+
+/// A runtime value.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Object {
+    Integer(i64),
+    Str(String),
+    Bool(bool),
+    Array(Vec<Object>),
+    Hash(Vec<(Object, Object)>),
+    Null,
+}
+
+/// Not all of these are real errors: `Return` is how a `return` statement
+/// unwinds out of nested blocks to the top level [`eval`], which turns it
+/// back into a normal value.
+#[derive(Debug, Clone)]
+pub enum EvalError {
+    Return(Object),
+    /// Not a real error: unwinds out of the current block to the nearest
+    /// enclosing `for` loop, carrying the loop's result value.
+    Break(Object),
+    /// Not a real error: unwinds out of the current block to the nearest
+    /// enclosing `for` loop, which resumes at the next iteration.
+    Continue,
+    UndefinedVariable(String),
+    TypeError(&'static str),
+    ArithmeticError(&'static str),
+}
+
+/// A scope of variable bindings, optionally chained to an outer scope so that
+/// nested blocks (`for`, `if`) can see variables declared above them.
+#[derive(Clone)]
+pub struct Environment {
+    bindings: HashMap<String, Object>,
+    outer: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment {
+            bindings: HashMap::new(),
+            outer: None,
+        }
+    }
+
+    pub fn new_enclosed(outer: Rc<RefCell<Environment>>) -> Environment {
+        Environment {
+            bindings: HashMap::new(),
+            outer: Some(outer),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Object> {
+        match self.bindings.get(name) {
+            Some(object) => Some(object.clone()),
+            None => self.outer.as_ref().and_then(|outer| outer.borrow().get(name)),
+        }
+    }
+
+    pub fn set(&mut self, name: &Identifier, value: Object) {
+        self.bindings.insert(name.name().to_string(), value);
+    }
+}
+
+/// Evaluates a top level block, unwrapping an early `return` into the value
+/// it carries rather than propagating it any further.
+pub fn eval(block: &Block, env: &Rc<RefCell<Environment>>) -> Result<Object, EvalError> {
+    match eval_block(block, env) {
+        Ok(value) => Ok(value),
+        Err(EvalError::Return(value)) => Ok(value),
+        Err(err) => Err(err),
+    }
+}
+
+fn eval_block(block: &Block, env: &Rc<RefCell<Environment>>) -> Result<Object, EvalError> {
+    let mut result = Object::Null;
+    for statement in block.statements() {
+        result = eval_statement(statement, env)?;
+    }
+    Ok(result)
+}
+
+fn eval_statement(statement: &Statement, env: &Rc<RefCell<Environment>>) -> Result<Object, EvalError> {
+    match statement {
+        Statement::Let(name, expr) => {
+            let value = eval_expression(expr, env)?;
+            env.borrow_mut().set(name, value);
+            Ok(Object::Null)
+        }
+        Statement::Return(expr) => {
+            let value = eval_expression(expr, env)?;
+            Err(EvalError::Return(value))
+        }
+        Statement::Break(expr) => {
+            let value = match expr {
+                Some(expr) => eval_expression(expr, env)?,
+                None => Object::Null,
+            };
+            Err(EvalError::Break(value))
+        }
+        Statement::Continue => Err(EvalError::Continue),
+        Statement::Expression(expr) => eval_expression(expr, env),
+    }
+}
+
+fn eval_expression(expr: &Expression, env: &Rc<RefCell<Environment>>) -> Result<Object, EvalError> {
+    match expr {
+        Expression::Identifier(id) => env
+            .borrow()
+            .get(id.name())
+            .ok_or_else(|| EvalError::UndefinedVariable(id.name().to_string())),
+        Expression::Literal(lit) => Ok(eval_literal(lit)),
+        Expression::Prefix(prefix, expr) => {
+            let value = eval_expression(expr, env)?;
+            eval_prefix(prefix, value)
+        }
+        Expression::Infix(Infix::And, left, right) => {
+            let left = eval_expression(left, env)?;
+            if is_truthy(&left) {
+                eval_expression(right, env)
+            } else {
+                Ok(left)
+            }
+        }
+        Expression::Infix(Infix::Or, left, right) => {
+            let left = eval_expression(left, env)?;
+            if is_truthy(&left) {
+                Ok(left)
+            } else {
+                eval_expression(right, env)
+            }
+        }
+        Expression::Infix(infix, left, right) => {
+            let left = eval_expression(left, env)?;
+            let right = eval_expression(right, env)?;
+            eval_infix(infix, left, right)
+        }
+        Expression::For(id, start, end, step, block) => eval_for(id, start, end, step, block, env),
+        Expression::IfExpr(cond, true_block, false_block) => {
+            eval_if(cond, true_block, false_block, env)
+        }
+        Expression::FunctionCall(_, _) => {
+            Err(EvalError::TypeError("function calls are not yet supported"))
+        }
+        Expression::Array(elements) => {
+            let mut values = Vec::with_capacity(elements.len());
+            for element in elements {
+                values.push(eval_expression(element, env)?);
+            }
+            Ok(Object::Array(values))
+        }
+        Expression::Hash(entries) => {
+            let mut values = Vec::with_capacity(entries.len());
+            for (key, value) in entries {
+                values.push((eval_literal(key), eval_expression(value, env)?));
+            }
+            Ok(Object::Hash(values))
+        }
+        Expression::Index(target, index) => eval_index(target, index, env),
+    }
+}
+
+fn eval_literal(literal: &Literal) -> Object {
+    match literal {
+        Literal::String(s) => Object::Str(s.clone()),
+        Literal::DecimalInt(n) => Object::Integer(*n),
+        Literal::HexInt(n) => Object::Integer(*n),
+        Literal::Bool(b) => Object::Bool(*b),
+    }
+}
+
+fn eval_prefix(prefix: &Prefix, value: Object) -> Result<Object, EvalError> {
+    match (prefix, value) {
+        (Prefix::Negate, Object::Integer(n)) => Ok(Object::Integer(-n)),
+        (Prefix::Bitflip, Object::Bool(b)) => Ok(Object::Bool(!b)),
+        (Prefix::Bitflip, Object::Integer(n)) => Ok(Object::Integer(!n)),
+        _ => Err(EvalError::TypeError("unsupported operand for prefix operator")),
+    }
+}
+
+fn eval_infix(infix: &Infix, left: Object, right: Object) -> Result<Object, EvalError> {
+    match (left, right) {
+        (Object::Integer(l), Object::Integer(r)) => eval_integer_infix(infix, l, r),
+        (Object::Str(l), Object::Str(r)) => eval_string_infix(infix, l, r),
+        _ => Err(EvalError::TypeError("mismatched operand types for infix operator")),
+    }
+}
+
+fn eval_integer_infix(infix: &Infix, left: i64, right: i64) -> Result<Object, EvalError> {
+    let result = match infix {
+        Infix::Add => Object::Integer(
+            left.checked_add(right)
+                .ok_or(EvalError::ArithmeticError("integer overflow in addition"))?,
+        ),
+        Infix::Subtract => Object::Integer(
+            left.checked_sub(right)
+                .ok_or(EvalError::ArithmeticError("integer overflow in subtraction"))?,
+        ),
+        Infix::Multiply => Object::Integer(
+            left.checked_mul(right)
+                .ok_or(EvalError::ArithmeticError("integer overflow in multiplication"))?,
+        ),
+        Infix::Divide => Object::Integer(
+            left.checked_div(right)
+                .ok_or(EvalError::ArithmeticError("division by zero or overflow"))?,
+        ),
+        Infix::Equal => Object::Bool(left == right),
+        Infix::NotEqual => Object::Bool(left != right),
+        Infix::GreaterThan => Object::Bool(left > right),
+        Infix::GreaterThanOrEqual => Object::Bool(left >= right),
+        Infix::LessThan => Object::Bool(left < right),
+        Infix::LessThanOrEqual => Object::Bool(left <= right),
+        Infix::And | Infix::Or => {
+            return Err(EvalError::TypeError(
+                "`and`/`or` short-circuit before reaching here",
+            ))
+        }
+    };
+    Ok(result)
+}
+
+fn eval_string_infix(infix: &Infix, left: String, right: String) -> Result<Object, EvalError> {
+    match infix {
+        Infix::Add => Ok(Object::Str(left + &right)),
+        Infix::Equal => Ok(Object::Bool(left == right)),
+        Infix::NotEqual => Ok(Object::Bool(left != right)),
+        _ => Err(EvalError::TypeError("unsupported operator for strings")),
+    }
+}
+
+fn eval_index(
+    target: &Expression,
+    index: &Expression,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, EvalError> {
+    let target = eval_expression(target, env)?;
+    let index = eval_expression(index, env)?;
+    match (target, index) {
+        (Object::Array(values), Object::Integer(i)) => {
+            let value = if i >= 0 {
+                values.get(i as usize).cloned()
+            } else {
+                None
+            };
+            Ok(value.unwrap_or(Object::Null))
+        }
+        (Object::Hash(entries), key) => Ok(entries
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v)
+            .unwrap_or(Object::Null)),
+        _ => Err(EvalError::TypeError("cannot index this value")),
+    }
+}
+
+fn is_truthy(object: &Object) -> bool {
+    !matches!(object, Object::Bool(false) | Object::Null)
+}
+
+fn as_integer(object: Object) -> Result<i64, EvalError> {
+    match object {
+        Object::Integer(n) => Ok(n),
+        _ => Err(EvalError::TypeError("expected an integer")),
+    }
+}
+
+fn eval_for(
+    id: &Identifier,
+    start: &Expression,
+    end: &Expression,
+    step: &Option<Box<Expression>>,
+    block: &Block,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, EvalError> {
+    let start = as_integer(eval_expression(start, env)?)?;
+    let end = as_integer(eval_expression(end, env)?)?;
+    let step = match step {
+        Some(expr) => as_integer(eval_expression(expr, env)?)?,
+        None => 1,
+    };
+    if step == 0 {
+        return Err(EvalError::ArithmeticError("for loop step must not be zero"));
+    }
+
+    let mut i = start;
+    let mut result = Object::Null;
+    while (step > 0 && i <= end) || (step < 0 && i >= end) {
+        let loop_env = Rc::new(RefCell::new(Environment::new_enclosed(env.clone())));
+        loop_env.borrow_mut().set(id, Object::Integer(i));
+        match eval_block(block, &loop_env) {
+            Ok(_) | Err(EvalError::Continue) => {}
+            Err(EvalError::Break(value)) => {
+                result = value;
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+        i = match i.checked_add(step) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    Ok(result)
+}
+
+fn eval_if(
+    cond: &Expression,
+    true_block: &Block,
+    false_block: &Option<Block>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, EvalError> {
+    let cond = eval_expression(cond, env)?;
+    if is_truthy(&cond) {
+        let block_env = Rc::new(RefCell::new(Environment::new_enclosed(env.clone())));
+        eval_block(true_block, &block_env)
+    } else if let Some(false_block) = false_block {
+        let block_env = Rc::new(RefCell::new(Environment::new_enclosed(env.clone())));
+        eval_block(false_block, &block_env)
+    } else {
+        Ok(Object::Null)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::Parser;
+    use lexer::Token;
+
+    fn eval_source(tokens: Vec<Token>) -> Result<Object, EvalError> {
+        let mut parser = Parser::new();
+        for token in tokens {
+            parser.feed(token).unwrap();
+        }
+        let block = parser.get_tree().unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+        eval(&block, &env)
+    }
+
+    #[test]
+    fn integer_literal() {
+        let result = eval_source(vec![Token::DecimalIntLiteral(42), Token::Semicolon]);
+        assert_eq!(result.unwrap(), Object::Integer(42));
+    }
+
+    #[test]
+    fn let_binding_and_lookup() {
+        let result = eval_source(vec![
+            Token::Let,
+            Token::Identifier("x".into()),
+            Token::Assign,
+            Token::DecimalIntLiteral(5),
+            Token::Semicolon,
+            Token::Identifier("x".into()),
+            Token::Plus,
+            Token::DecimalIntLiteral(1),
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Integer(6));
+    }
+
+    #[test]
+    fn undefined_variable_errors() {
+        let result = eval_source(vec![Token::Identifier("missing".into()), Token::Semicolon]);
+        match result {
+            Err(EvalError::UndefinedVariable(name)) => assert_eq!(name, "missing"),
+            other => panic!("expected UndefinedVariable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn type_error_on_mismatched_operands() {
+        let result = eval_source(vec![
+            Token::DecimalIntLiteral(1),
+            Token::Plus,
+            Token::True,
+            Token::Semicolon,
+        ]);
+        assert!(matches!(result, Err(EvalError::TypeError(_))));
+    }
+
+    #[test]
+    fn early_return_short_circuits_the_block() {
+        // return 1; 2;
+        let result = eval_source(vec![
+            Token::Return,
+            Token::DecimalIntLiteral(1),
+            Token::Semicolon,
+            Token::DecimalIntLiteral(2),
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Integer(1));
+    }
+
+    #[test]
+    fn nested_scope_sees_outer_bindings() {
+        // let x = 1; if (true) { x; };
+        let result = eval_source(vec![
+            Token::Let,
+            Token::Identifier("x".into()),
+            Token::Assign,
+            Token::DecimalIntLiteral(1),
+            Token::Semicolon,
+            Token::If,
+            Token::LeftParen,
+            Token::True,
+            Token::RightParen,
+            Token::LeftBrace,
+            Token::Identifier("x".into()),
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Integer(1));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error_not_a_panic() {
+        let result = eval_source(vec![
+            Token::DecimalIntLiteral(1),
+            Token::Slash,
+            Token::DecimalIntLiteral(0),
+            Token::Semicolon,
+        ]);
+        assert!(matches!(result, Err(EvalError::ArithmeticError(_))));
+    }
+
+    #[test]
+    fn for_loop_break_yields_its_value() {
+        // for i in 0 to 10 { break i; };
+        let result = eval_source(vec![
+            Token::For,
+            Token::Identifier("i".into()),
+            Token::In,
+            Token::DecimalIntLiteral(0),
+            Token::To,
+            Token::DecimalIntLiteral(10),
+            Token::LeftBrace,
+            Token::Break,
+            Token::Identifier("i".into()),
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Integer(0));
+    }
+
+    #[test]
+    fn for_loop_continue_does_not_abort_the_loop() {
+        // for i in 0 to 5 { if (i == 1) { continue; }; if (i == 3) { break i; }; };
+        //
+        // If `continue` were mistakenly treated like `break`, the loop would
+        // stop at i == 1 and the `break` at i == 3 would never run.
+        let result = eval_source(vec![
+            Token::For,
+            Token::Identifier("i".into()),
+            Token::In,
+            Token::DecimalIntLiteral(0),
+            Token::To,
+            Token::DecimalIntLiteral(5),
+            Token::LeftBrace,
+            Token::If,
+            Token::LeftParen,
+            Token::Identifier("i".into()),
+            Token::Equal,
+            Token::DecimalIntLiteral(1),
+            Token::RightParen,
+            Token::LeftBrace,
+            Token::Continue,
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::Semicolon,
+            Token::If,
+            Token::LeftParen,
+            Token::Identifier("i".into()),
+            Token::Equal,
+            Token::DecimalIntLiteral(3),
+            Token::RightParen,
+            Token::LeftBrace,
+            Token::Break,
+            Token::Identifier("i".into()),
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Integer(3));
+    }
+
+    #[test]
+    fn and_short_circuits_without_evaluating_rhs() {
+        // false and undefined;
+        let result = eval_source(vec![
+            Token::False,
+            Token::And,
+            Token::Identifier("undefined".into()),
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Bool(false));
+    }
+
+    #[test]
+    fn or_short_circuits_without_evaluating_rhs() {
+        // true or undefined;
+        let result = eval_source(vec![
+            Token::True,
+            Token::Or,
+            Token::Identifier("undefined".into()),
+            Token::Semicolon,
+        ]);
+        assert_eq!(result.unwrap(), Object::Bool(true));
+    }
+
+    #[test]
+    fn for_loop_with_zero_step_is_an_error() {
+        let result = eval_source(vec![
+            Token::For,
+            Token::Identifier("i".into()),
+            Token::In,
+            Token::DecimalIntLiteral(0),
+            Token::To,
+            Token::DecimalIntLiteral(10),
+            Token::Step,
+            Token::DecimalIntLiteral(0),
+            Token::LeftBrace,
+            Token::RightBrace,
+            Token::Semicolon,
+        ]);
+        assert!(matches!(result, Err(EvalError::ArithmeticError(_))));
+    }
+}
@@ -0,0 +1,6 @@
+//! monotronian
+//!
+//! A small scripting language, designed to run on the Monotron.
+
+pub mod eval;
+pub mod parser;
@@ -5,7 +5,16 @@
 //! It is designed to parse either a function, or an immediate expression.
 //! The resulting AST can then either be executed immediately, or stored in memory for later execution.
 //!
-//! The conversion back to text currently does not support indentation.
+//! Expressions are parsed with a Pratt (precedence-climbing) parser: see
+//! `Parser::parse_expression` and `infix_binding_power` for the binding power
+//! table that drives it.
+//!
+//! Conversion back to text comes in two flavours: the `Display` impls render
+//! a flat form with no indentation (nested bodies, calls, arrays and hashes
+//! still break across lines, and each statement is followed by a blank
+//! line), while `Block::pretty`/
+//! `Statement::pretty`/`Expression::pretty` render an indented form suitable
+//! for showing a human the structure of nested `for`/`if` bodies.
 
 use core::fmt;
 use lexer::Token;
@@ -14,11 +23,23 @@ use lexer::Token;
 #[derive(PartialEq, Debug, Clone)]
 pub struct Block(Vec<Statement>);
 
+impl Block {
+    pub(crate) fn statements(&self) -> &[Statement] {
+        &self.0
+    }
+}
+
 /// Our program is made of statements.
 #[derive(PartialEq, Debug, Clone)]
 pub enum Statement {
     Let(Identifier, Expression),
     Return(Expression),
+    /// Exits the nearest enclosing `for` loop, optionally yielding a value as
+    /// the loop's result.
+    Break(Option<Expression>),
+    /// Skips straight to the next iteration of the nearest enclosing `for`
+    /// loop.
+    Continue,
     Expression(Expression),
 }
 
@@ -26,6 +47,12 @@ pub enum Statement {
 #[derive(PartialEq, Debug, Clone)]
 pub struct Identifier(String);
 
+impl Identifier {
+    pub(crate) fn name(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Expressions are how things are calculated
 #[derive(PartialEq, Debug, Clone)]
 pub enum Expression {
@@ -63,6 +90,12 @@ pub enum Infix {
     GreaterThanOrEqual,
     LessThan,
     LessThanOrEqual,
+    /// Short-circuits: the right-hand side is only evaluated if the left is
+    /// truthy.
+    And,
+    /// Short-circuits: the right-hand side is only evaluated if the left is
+    /// falsy.
+    Or,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -79,26 +112,452 @@ pub enum Literal {
     Bool(bool),
 }
 
+/// A parse failure, with enough context for a REPL to point at the offending
+/// token.
 #[derive(Debug, Clone)]
 pub enum Error {
-    SyntaxError,
+    /// A token was found where `expected` was required.
+    UnexpectedToken {
+        found: Token,
+        expected: &'static str,
+        position: usize,
+    },
+    /// Input ran out while `expected` was still required.
+    UnexpectedEof { expected: &'static str },
+    /// Input ran out with nothing more specific to say about it, e.g. a block
+    /// that was never closed.
+    UnexpectedEnd,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnexpectedToken {
+                found,
+                expected,
+                position,
+            } => write!(
+                fmt,
+                "unexpected token {:?} at position {}, expected {}",
+                found, position, expected
+            ),
+            Error::UnexpectedEof { expected } => {
+                write!(fmt, "unexpected end of input, expected {}", expected)
+            }
+            Error::UnexpectedEnd => write!(fmt, "unexpected end of input"),
+        }
+    }
+}
+
+/// Binding power of the call `(` and index `[` postfix operators. These always
+/// bind tighter than any infix operator.
+const CALL_BP: u8 = 7;
+const INDEX_BP: u8 = 7;
+
+/// Binding power used when recursing into a prefix operator's operand, e.g.
+/// the `-` in `-1 + 2` should only ever swallow the `1`.
+const PREFIX_BP: u8 = 6;
+
+/// Looks up the binding power of an infix operator token, also returning the
+/// `Infix` it corresponds to. Returns `None` for tokens that aren't infix
+/// operators at all.
+fn infix_binding_power(token: &Token) -> Option<(Infix, u8)> {
+    let infix = match token {
+        Token::And => Infix::And,
+        Token::Or => Infix::Or,
+        Token::Equal => Infix::Equal,
+        Token::NotEqual => Infix::NotEqual,
+        Token::LessThan => Infix::LessThan,
+        Token::LessThanOrEqual => Infix::LessThanOrEqual,
+        Token::GreaterThan => Infix::GreaterThan,
+        Token::GreaterThanOrEqual => Infix::GreaterThanOrEqual,
+        Token::Plus => Infix::Add,
+        Token::Minus => Infix::Subtract,
+        Token::Star => Infix::Multiply,
+        Token::Slash => Infix::Divide,
+        _ => return None,
+    };
+    let bp = match infix {
+        Infix::And | Infix::Or => 1,
+        Infix::Equal | Infix::NotEqual => 2,
+        Infix::LessThan | Infix::LessThanOrEqual | Infix::GreaterThan | Infix::GreaterThanOrEqual => 3,
+        Infix::Add | Infix::Subtract => 4,
+        Infix::Multiply | Infix::Divide => 5,
+    };
+    Some((infix, bp))
 }
 
+/// A streaming parser: tokens are fed in one at a time via [`Parser::feed`],
+/// then [`Parser::get_tree`] runs a Pratt (precedence-climbing) parse over
+/// everything that's been buffered so far.
 pub struct Parser {
-    _state: bool,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Parser {
     pub fn new() -> Parser {
-        Parser { _state: true }
+        Parser {
+            tokens: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    pub fn feed(&mut self, token: Token) -> Result<(), Error> {
+        self.tokens.push(token);
+        Ok(())
+    }
+
+    pub fn get_tree(mut self) -> Result<Block, Error> {
+        let mut statements = Vec::new();
+        while self.peek().is_some() {
+            statements.push(self.parse_statement()?);
+        }
+        Ok(Block(statements))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, token: &Token, expected: &'static str) -> Result<(), Error> {
+        match self.tokens.get(self.pos) {
+            Some(found) if found == token => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(found) => Err(Error::UnexpectedToken {
+                found: found.clone(),
+                expected,
+                position: self.pos,
+            }),
+            None => Err(Error::UnexpectedEof { expected }),
+        }
+    }
+
+    /// Builds an error describing what was expected at the current position,
+    /// without consuming anything.
+    fn error_here(&self, expected: &'static str) -> Error {
+        match self.tokens.get(self.pos) {
+            Some(found) => Error::UnexpectedToken {
+                found: found.clone(),
+                expected,
+                position: self.pos,
+            },
+            None => Error::UnexpectedEof { expected },
+        }
+    }
+
+    /// Consumes an identifier token, or errors pointing at whatever was found
+    /// instead.
+    fn expect_identifier(&mut self, expected: &'static str) -> Result<Identifier, Error> {
+        let error = self.error_here(expected);
+        match self.advance() {
+            Some(Token::Identifier(name)) => Ok(Identifier(name)),
+            _ => Err(error),
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, Error> {
+        match self.peek() {
+            Some(Token::Let) => self.parse_let_statement(),
+            Some(Token::Return) => self.parse_return_statement(),
+            Some(Token::Break) => self.parse_break_statement(),
+            Some(Token::Continue) => self.parse_continue_statement(),
+            _ => {
+                let expr = self.parse_expression(0)?;
+                // `for`/`if` already end in a closing `}`, so the trailing
+                // `;` that terminates every other expression statement is
+                // optional for them.
+                match expr {
+                    Expression::For(..) | Expression::IfExpr(..) => {
+                        if let Some(Token::Semicolon) = self.peek() {
+                            self.advance();
+                        }
+                    }
+                    _ => {
+                        self.expect(&Token::Semicolon, "`;` to end statement")?;
+                    }
+                }
+                Ok(Statement::Expression(expr))
+            }
+        }
+    }
+
+    fn parse_let_statement(&mut self) -> Result<Statement, Error> {
+        self.advance(); // `let`
+        let name = self.expect_identifier("an identifier after `let`")?;
+        self.expect(&Token::Assign, "`=` after identifier")?;
+        let expr = self.parse_expression(0)?;
+        self.expect(&Token::Semicolon, "`;` to end statement")?;
+        Ok(Statement::Let(name, expr))
+    }
+
+    fn parse_return_statement(&mut self) -> Result<Statement, Error> {
+        self.advance(); // `return`
+        let expr = self.parse_expression(0)?;
+        self.expect(&Token::Semicolon, "`;` to end statement")?;
+        Ok(Statement::Return(expr))
+    }
+
+    fn parse_break_statement(&mut self) -> Result<Statement, Error> {
+        self.advance(); // `break`
+        if let Some(Token::Semicolon) = self.peek() {
+            self.advance();
+            return Ok(Statement::Break(None));
+        }
+        let expr = self.parse_expression(0)?;
+        self.expect(&Token::Semicolon, "`;` to end statement")?;
+        Ok(Statement::Break(Some(expr)))
+    }
+
+    fn parse_continue_statement(&mut self) -> Result<Statement, Error> {
+        self.advance(); // `continue`
+        self.expect(&Token::Semicolon, "`;` to end statement")?;
+        Ok(Statement::Continue)
+    }
+
+    fn parse_block_until_brace(&mut self) -> Result<Block, Error> {
+        let mut statements = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::RightBrace) => break,
+                Some(_) => statements.push(self.parse_statement()?),
+                None => return Err(Error::UnexpectedEnd),
+            }
+        }
+        Ok(Block(statements))
+    }
+
+    /// Parses an expression using precedence climbing: an atom ("nud") is
+    /// parsed first, then we loop consuming infix/postfix operators whose
+    /// binding power is at least `min_bp`, recursing with a slightly higher
+    /// binding power to get left-associativity.
+    fn parse_expression(&mut self, min_bp: u8) -> Result<Expression, Error> {
+        let mut left = self.parse_prefix()?;
+
+        loop {
+            match self.peek() {
+                Some(Token::LeftParen) if CALL_BP >= min_bp => {
+                    self.advance();
+                    let args = self.parse_call_arguments()?;
+                    left = Expression::FunctionCall(Box::new(left), args);
+                }
+                Some(Token::LeftSquare) if INDEX_BP >= min_bp => {
+                    self.advance();
+                    let index = self.parse_expression(0)?;
+                    self.expect(&Token::RightSquare, "`]` to close index")?;
+                    left = Expression::Index(Box::new(left), Box::new(index));
+                }
+                Some(token) => match infix_binding_power(token) {
+                    Some((infix, bp)) if bp >= min_bp => {
+                        self.advance();
+                        let right = self.parse_expression(bp + 1)?;
+                        left = Expression::Infix(infix, Box::new(left), Box::new(right));
+                    }
+                    _ => break,
+                },
+                None => break,
+            }
+        }
+
+        Ok(left)
+    }
+
+    /// Parses a prefix position atom: identifiers, literals, parenthesised
+    /// groups, unary operators, arrays, hashes and the `for`/`if` keywords.
+    fn parse_prefix(&mut self) -> Result<Expression, Error> {
+        let start_pos = self.pos;
+        match self.advance() {
+            Some(Token::Identifier(name)) => Ok(Expression::Identifier(Identifier(name))),
+            Some(Token::DecimalIntLiteral(n)) => Ok(Expression::Literal(Literal::DecimalInt(n))),
+            Some(Token::HexIntLiteral(n)) => Ok(Expression::Literal(Literal::HexInt(n))),
+            Some(Token::StringLiteral(s)) => Ok(Expression::Literal(Literal::String(s))),
+            Some(Token::True) => Ok(Expression::Literal(Literal::Bool(true))),
+            Some(Token::False) => Ok(Expression::Literal(Literal::Bool(false))),
+            Some(Token::Minus) => {
+                let expr = self.parse_expression(PREFIX_BP)?;
+                Ok(Expression::Prefix(Prefix::Negate, Box::new(expr)))
+            }
+            Some(Token::Bang) => {
+                let expr = self.parse_expression(PREFIX_BP)?;
+                Ok(Expression::Prefix(Prefix::Bitflip, Box::new(expr)))
+            }
+            Some(Token::LeftParen) => {
+                let expr = self.parse_expression(0)?;
+                self.expect(&Token::RightParen, "`)` to close group")?;
+                Ok(expr)
+            }
+            Some(Token::LeftSquare) => self.parse_array(),
+            Some(Token::LeftBrace) => self.parse_hash(),
+            Some(Token::For) => self.parse_for(),
+            Some(Token::If) => self.parse_if(),
+            Some(found) => Err(Error::UnexpectedToken {
+                found,
+                expected: "an expression",
+                position: start_pos,
+            }),
+            None => Err(Error::UnexpectedEof {
+                expected: "an expression",
+            }),
+        }
     }
 
-    pub fn feed(&mut self, _token: Token) -> Result<(), Error> {
-        Err(Error::SyntaxError)
+    fn parse_call_arguments(&mut self) -> Result<Vec<Expression>, Error> {
+        let mut args = Vec::new();
+        if let Some(Token::RightParen) = self.peek() {
+            self.advance();
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expression(0)?);
+            let start_pos = self.pos;
+            match self.advance() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParen) => break,
+                Some(found) => {
+                    return Err(Error::UnexpectedToken {
+                        found,
+                        expected: "`,` or `)` in argument list",
+                        position: start_pos,
+                    })
+                }
+                None => {
+                    return Err(Error::UnexpectedEof {
+                        expected: "`,` or `)` in argument list",
+                    })
+                }
+            }
+        }
+        Ok(args)
+    }
+
+    fn parse_array(&mut self) -> Result<Expression, Error> {
+        let mut elements = Vec::new();
+        if let Some(Token::RightSquare) = self.peek() {
+            self.advance();
+            return Ok(Expression::Array(elements));
+        }
+        loop {
+            elements.push(self.parse_expression(0)?);
+            let start_pos = self.pos;
+            match self.advance() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightSquare) => break,
+                Some(found) => {
+                    return Err(Error::UnexpectedToken {
+                        found,
+                        expected: "`,` or `]` in array",
+                        position: start_pos,
+                    })
+                }
+                None => {
+                    return Err(Error::UnexpectedEof {
+                        expected: "`,` or `]` in array",
+                    })
+                }
+            }
+        }
+        Ok(Expression::Array(elements))
+    }
+
+    fn parse_hash(&mut self) -> Result<Expression, Error> {
+        let mut entries = Vec::new();
+        if let Some(Token::RightBrace) = self.peek() {
+            self.advance();
+            return Ok(Expression::Hash(entries));
+        }
+        loop {
+            let key_pos = self.pos;
+            let key = match self.parse_prefix()? {
+                Expression::Literal(lit) => lit,
+                _ => {
+                    return Err(Error::UnexpectedToken {
+                        found: self.tokens[key_pos].clone(),
+                        expected: "a literal hash key",
+                        position: key_pos,
+                    })
+                }
+            };
+            self.expect(&Token::Colon, "`:` after hash key")?;
+            let value = self.parse_expression(0)?;
+            entries.push((key, value));
+            let start_pos = self.pos;
+            match self.advance() {
+                Some(Token::Comma) => continue,
+                Some(Token::RightBrace) => break,
+                Some(found) => {
+                    return Err(Error::UnexpectedToken {
+                        found,
+                        expected: "`,` or `}` in hash",
+                        position: start_pos,
+                    })
+                }
+                None => {
+                    return Err(Error::UnexpectedEof {
+                        expected: "`,` or `}` in hash",
+                    })
+                }
+            }
+        }
+        Ok(Expression::Hash(entries))
     }
 
-    pub fn get_tree(self) -> Result<Block, Error> {
-        Err(Error::SyntaxError)
+    fn parse_for(&mut self) -> Result<Expression, Error> {
+        let name = self.expect_identifier("a loop variable name after `for`")?;
+        self.expect(&Token::In, "`in` after loop variable")?;
+        let start = self.parse_expression(0)?;
+        self.expect(&Token::To, "`to` after loop start value")?;
+        let end = self.parse_expression(0)?;
+        let step = if let Some(Token::Step) = self.peek() {
+            self.advance();
+            Some(Box::new(self.parse_expression(0)?))
+        } else {
+            None
+        };
+        self.expect(&Token::LeftBrace, "`{` to start loop body")?;
+        let block = self.parse_block_until_brace()?;
+        self.expect(&Token::RightBrace, "`}` to end loop body")?;
+        Ok(Expression::For(
+            name,
+            Box::new(start),
+            Box::new(end),
+            step,
+            Box::new(block),
+        ))
+    }
+
+    fn parse_if(&mut self) -> Result<Expression, Error> {
+        self.expect(&Token::LeftParen, "`(` after `if`")?;
+        let cond = self.parse_expression(0)?;
+        self.expect(&Token::RightParen, "`)` after condition")?;
+        self.expect(&Token::LeftBrace, "`{` to start if body")?;
+        let true_block = self.parse_block_until_brace()?;
+        self.expect(&Token::RightBrace, "`}` to end if body")?;
+        let false_block = if let Some(Token::Else) = self.peek() {
+            self.advance();
+            self.expect(&Token::LeftBrace, "`{` to start else body")?;
+            let block = self.parse_block_until_brace()?;
+            self.expect(&Token::RightBrace, "`}` to end else body")?;
+            Some(block)
+        } else {
+            None
+        };
+        Ok(Expression::IfExpr(Box::new(cond), true_block, false_block))
     }
 }
 
@@ -116,6 +575,9 @@ impl fmt::Display for Statement {
         match self {
             Statement::Let(id, expr) => writeln!(fmt, "let {} = {};", id, expr)?,
             Statement::Return(expr) => writeln!(fmt, "return {};", expr)?,
+            Statement::Break(Some(expr)) => writeln!(fmt, "break {};", expr)?,
+            Statement::Break(None) => writeln!(fmt, "break;")?,
+            Statement::Continue => writeln!(fmt, "continue;")?,
             Statement::Expression(expr) => writeln!(fmt, "{};", expr)?,
         }
         Ok(())
@@ -160,6 +622,8 @@ impl fmt::Display for Infix {
             Infix::GreaterThanOrEqual => write!(fmt, ">="),
             Infix::LessThan => write!(fmt, "<"),
             Infix::LessThanOrEqual => write!(fmt, "<="),
+            Infix::And => write!(fmt, "and"),
+            Infix::Or => write!(fmt, "or"),
         }
     }
 }
@@ -226,19 +690,266 @@ impl fmt::Display for Expression {
     }
 }
 
+fn indent_str(indent: usize) -> String {
+    "    ".repeat(indent)
+}
+
+impl Block {
+    /// Renders this block one statement per line, each prefixed with
+    /// `indent` levels of four-space indentation.
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        for statement in self.0.iter() {
+            out.push_str(&indent_str(indent));
+            out.push_str(&statement.pretty(indent));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Statement {
+    pub fn pretty(&self, indent: usize) -> String {
+        match self {
+            Statement::Let(id, expr) => format!("let {} = {};", id, expr.pretty(indent)),
+            Statement::Return(expr) => format!("return {};", expr.pretty(indent)),
+            Statement::Break(Some(expr)) => format!("break {};", expr.pretty(indent)),
+            Statement::Break(None) => "break;".to_string(),
+            Statement::Continue => "continue;".to_string(),
+            Statement::Expression(expr) => format!("{};", expr.pretty(indent)),
+        }
+    }
+}
+
+impl Expression {
+    /// Renders this expression, indenting any nested block (`for`/`if`
+    /// bodies) one level deeper than `indent` and closing braces back at
+    /// `indent`.
+    pub fn pretty(&self, indent: usize) -> String {
+        match self {
+            Expression::Identifier(id) => id.to_string(),
+            Expression::Literal(lit) => lit.to_string(),
+            Expression::Prefix(prefix, expr) => format!("{}{}", prefix, expr.pretty(indent)),
+            Expression::Infix(infix, expr_l, expr_r) => {
+                format!("{} {} {}", expr_l.pretty(indent), infix, expr_r.pretty(indent))
+            }
+            Expression::For(id, start, end, step, block) => {
+                let mut out = match step {
+                    Some(s) => format!(
+                        "for {} in {} to {} step {} {{\n",
+                        id,
+                        start.pretty(indent),
+                        end.pretty(indent),
+                        s.pretty(indent)
+                    ),
+                    None => format!(
+                        "for {} in {} to {} {{\n",
+                        id,
+                        start.pretty(indent),
+                        end.pretty(indent)
+                    ),
+                };
+                out.push_str(&block.pretty(indent + 1));
+                out.push_str(&indent_str(indent));
+                out.push('}');
+                out
+            }
+            Expression::IfExpr(expr, true_block, false_block) => {
+                let mut out = format!("if ({}) {{\n", expr.pretty(indent));
+                out.push_str(&true_block.pretty(indent + 1));
+                out.push_str(&indent_str(indent));
+                out.push('}');
+                if let Some(f) = false_block {
+                    out.push_str(" else {\n");
+                    out.push_str(&f.pretty(indent + 1));
+                    out.push_str(&indent_str(indent));
+                    out.push('}');
+                }
+                out
+            }
+            Expression::FunctionCall(expr, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.pretty(indent))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({})", expr.pretty(indent), args)
+            }
+            Expression::Array(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|elem| elem.pretty(indent))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("[{}]", elements)
+            }
+            Expression::Hash(map) => {
+                let entries = map
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value.pretty(indent)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{{{}}}", entries)
+            }
+            Expression::Index(array, index) => {
+                format!("{}[{}]", array.pretty(indent), index.pretty(indent))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn parse(tokens: Vec<Token>) -> Block {
+        let mut p = Parser::new();
+        for token in tokens {
+            p.feed(token).unwrap();
+        }
+        p.get_tree().unwrap()
+    }
+
     #[test]
     fn make_parser() {
         let mut _p = Parser::new();
-        // assert!(p.feed(Token::DecimalIntLiteral(123)).is_ok());
-        // let result = p.get_tree();
-        // // This is our block
-        // let expected = vec! [
-        //     Statement::Expression(Expression::Literal(Literal::DecimalInt(123)))
-        // ];
-        // assert_eq!(result.unwrap().0, expected);
+    }
+
+    #[test]
+    fn bare_literal_expression() {
+        let block = parse(vec![Token::DecimalIntLiteral(123), Token::Semicolon]);
+        let expected = vec![Statement::Expression(Expression::Literal(
+            Literal::DecimalInt(123),
+        ))];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn let_statement() {
+        let block = parse(vec![
+            Token::Let,
+            Token::Identifier("x".into()),
+            Token::Assign,
+            Token::DecimalIntLiteral(1),
+            Token::Semicolon,
+        ]);
+        let expected = vec![Statement::Let(
+            Identifier("x".into()),
+            Expression::Literal(Literal::DecimalInt(1)),
+        )];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn operator_precedence() {
+        // 1 + 2 * 3;
+        let block = parse(vec![
+            Token::DecimalIntLiteral(1),
+            Token::Plus,
+            Token::DecimalIntLiteral(2),
+            Token::Star,
+            Token::DecimalIntLiteral(3),
+            Token::Semicolon,
+        ]);
+        let one = || Box::new(Expression::Literal(Literal::DecimalInt(1)));
+        let two = || Box::new(Expression::Literal(Literal::DecimalInt(2)));
+        let three = || Box::new(Expression::Literal(Literal::DecimalInt(3)));
+        let expected = vec![Statement::Expression(Expression::Infix(
+            Infix::Add,
+            one(),
+            Box::new(Expression::Infix(Infix::Multiply, two(), three())),
+        ))];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn function_call() {
+        let block = parse(vec![
+            Token::Identifier("foo".into()),
+            Token::LeftParen,
+            Token::DecimalIntLiteral(1),
+            Token::Comma,
+            Token::DecimalIntLiteral(2),
+            Token::RightParen,
+            Token::Semicolon,
+        ]);
+        let expected = vec![Statement::Expression(Expression::FunctionCall(
+            Box::new(Expression::Identifier(Identifier("foo".into()))),
+            vec![
+                Expression::Literal(Literal::DecimalInt(1)),
+                Expression::Literal(Literal::DecimalInt(2)),
+            ],
+        ))];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn break_and_continue() {
+        let block = parse(vec![
+            Token::Break,
+            Token::DecimalIntLiteral(1),
+            Token::Semicolon,
+            Token::Continue,
+            Token::Semicolon,
+        ]);
+        let expected = vec![
+            Statement::Break(Some(Expression::Literal(Literal::DecimalInt(1)))),
+            Statement::Continue,
+        ];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn and_binds_looser_than_comparison() {
+        // x > 0 and x < 10;
+        let block = parse(vec![
+            Token::Identifier("x".into()),
+            Token::GreaterThan,
+            Token::DecimalIntLiteral(0),
+            Token::And,
+            Token::Identifier("x".into()),
+            Token::LessThan,
+            Token::DecimalIntLiteral(10),
+            Token::Semicolon,
+        ]);
+        let x = || Box::new(Expression::Identifier(Identifier("x".into())));
+        let zero = || Box::new(Expression::Literal(Literal::DecimalInt(0)));
+        let ten = || Box::new(Expression::Literal(Literal::DecimalInt(10)));
+        let expected = vec![Statement::Expression(Expression::Infix(
+            Infix::And,
+            Box::new(Expression::Infix(Infix::GreaterThan, x(), zero())),
+            Box::new(Expression::Infix(Infix::LessThan, x(), ten())),
+        ))];
+        assert_eq!(block.0, expected);
+    }
+
+    #[test]
+    fn pretty_prints_nested_for_with_indentation() {
+        // for i in 0 to 10 { if (i) { print(i); } }
+        let block = parse(vec![
+            Token::For,
+            Token::Identifier("i".into()),
+            Token::In,
+            Token::DecimalIntLiteral(0),
+            Token::To,
+            Token::DecimalIntLiteral(10),
+            Token::LeftBrace,
+            Token::If,
+            Token::LeftParen,
+            Token::Identifier("i".into()),
+            Token::RightParen,
+            Token::LeftBrace,
+            Token::Identifier("print".into()),
+            Token::LeftParen,
+            Token::Identifier("i".into()),
+            Token::RightParen,
+            Token::Semicolon,
+            Token::RightBrace,
+            Token::RightBrace,
+            Token::Semicolon,
+        ]);
+
+        let expected = "for i in 0 to 10 {\n    if (i) {\n        print(i);\n    };\n};\n";
+        assert_eq!(block.pretty(0), expected);
     }
 }